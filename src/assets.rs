@@ -0,0 +1,107 @@
+use std::fs;
+use std::io;
+
+use directories::ProjectDirs;
+use syntect::dumps::{dump_to_file, from_binary, from_reader};
+use syntect::highlighting::ThemeSet;
+use syntect::parsing::SyntaxSet;
+
+/// The syntaxes and themes delta highlights with, either the ones it ships
+/// with or a user's own, loaded from their config directory.
+///
+/// Mirrors bat's `HighlightingAssets`: building a `SyntaxSet`/`ThemeSet` from
+/// `.sublime-syntax`/`.tmTheme` files is slow, so `build_cache` dumps a
+/// serialized copy to the cache directory and later startups load from that
+/// binary blob instead.
+pub struct HighlightingAssets {
+    pub syntax_set: SyntaxSet,
+    pub theme_set: ThemeSet,
+}
+
+impl HighlightingAssets {
+    /// Load the cached syntaxes/themes if present, otherwise fall back to the
+    /// syntaxes/themes built into the delta binary.
+    pub fn new() -> Self {
+        Self::from_cache().unwrap_or_else(|_| Self::from_binary())
+    }
+
+    /// Load delta's built-in syntaxes and themes, dumped into the binary at
+    /// compile time.
+    pub fn from_binary() -> Self {
+        HighlightingAssets {
+            syntax_set: from_binary(include_bytes!("../assets/syntaxes.bin")),
+            theme_set: from_binary(include_bytes!("../assets/themes.bin")),
+        }
+    }
+
+    /// Load the serialized syntaxes/themes previously written by
+    /// `build_cache` from the user's cache directory.
+    pub fn from_cache() -> io::Result<Self> {
+        let cache_dir = Self::cache_dir()?;
+        let syntax_set = from_reader(io::BufReader::new(fs::File::open(
+            cache_dir.join("syntaxes.bin"),
+        )?))
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        let theme_set = from_reader(io::BufReader::new(fs::File::open(
+            cache_dir.join("themes.bin"),
+        )?))
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        Ok(HighlightingAssets {
+            syntax_set,
+            theme_set,
+        })
+    }
+
+    /// Load syntaxes/themes from the user's `config_dir()/syntaxes` and
+    /// `config_dir()/themes` folders, on top of delta's built-in ones.
+    pub fn from_files() -> io::Result<Self> {
+        let mut assets = Self::from_binary();
+        let config_dir = Self::config_dir()?;
+
+        let syntaxes_dir = config_dir.join("syntaxes");
+        if syntaxes_dir.is_dir() {
+            let mut builder = assets.syntax_set.into_builder();
+            builder
+                .add_from_folder(&syntaxes_dir, true)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+            assets.syntax_set = builder.build();
+        }
+
+        let themes_dir = config_dir.join("themes");
+        if themes_dir.is_dir() {
+            let user_themes = ThemeSet::load_from_folder(&themes_dir)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+            assets.theme_set.themes.extend(user_themes.themes);
+        }
+
+        Ok(assets)
+    }
+
+    /// (Re)build the on-disk cache from `from_files()`, for the
+    /// `delta --build-cache` command path. Subsequent startups use
+    /// `from_cache()` instead of re-parsing `.sublime-syntax`/`.tmTheme`
+    /// files.
+    pub fn build_cache() -> io::Result<()> {
+        let assets = Self::from_files()?;
+        let cache_dir = Self::cache_dir()?;
+        fs::create_dir_all(&cache_dir)?;
+        dump_to_file(&assets.syntax_set, cache_dir.join("syntaxes.bin"))
+            .map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
+        dump_to_file(&assets.theme_set, cache_dir.join("themes.bin"))
+            .map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
+        Ok(())
+    }
+
+    fn project_dirs() -> io::Result<ProjectDirs> {
+        ProjectDirs::from("", "", "delta")
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "could not determine home directory"))
+    }
+
+    fn config_dir() -> io::Result<std::path::PathBuf> {
+        Self::project_dirs().map(|d| d.config_dir().to_path_buf())
+    }
+
+    fn cache_dir() -> io::Result<std::path::PathBuf> {
+        Self::project_dirs().map(|d| d.cache_dir().to_path_buf())
+    }
+}
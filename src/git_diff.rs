@@ -0,0 +1,55 @@
+use std::io;
+use std::path::Path;
+
+use git2::{DiffFormat, Repository};
+
+use crate::assets::HighlightingAssets;
+use crate::paint::Config;
+use crate::state_machine::delta;
+
+/// Alternate entry point to `delta <path>`: open the repository at `path`,
+/// compute its working-tree-vs-HEAD diff with `git2`, and feed the result
+/// through the same `delta()` state machine used for piped `git diff`
+/// output. Lets delta show syntax-highlighted changes standalone, without a
+/// separate `git diff |` pipeline.
+pub fn delta_repo(path: &Path, config: &Config, assets: &HighlightingAssets) -> io::Result<()> {
+    let lines = diff_lines(path)?;
+    delta(lines.into_iter(), config, assets)
+}
+
+/// Format the working-tree-vs-HEAD diff for the repository at `path` as
+/// unified-diff text lines, the same shape `delta()` expects from a piped
+/// `git diff`. Line callbacks from `git2` are formatted exactly like git's
+/// own textual unified diff, so the `diff --`, `@@`, `+`/`-` parsing in
+/// `delta()` keeps working unchanged.
+fn diff_lines(path: &Path) -> io::Result<Vec<String>> {
+    let repo = Repository::open(path).map_err(to_io_error)?;
+    // A repository with no commits yet has no HEAD to diff against; diff
+    // against the empty tree instead, so every tracked file shows up as
+    // added. Untracked files are not included.
+    let head_tree = match repo.head().and_then(|head| head.peel_to_tree()) {
+        Ok(tree) => Some(tree),
+        Err(_) => None,
+    };
+    let diff = repo
+        .diff_tree_to_workdir_with_index(head_tree.as_ref(), None)
+        .map_err(to_io_error)?;
+
+    let mut lines = Vec::new();
+    diff.print(DiffFormat::Patch, |_delta, _hunk, line| {
+        let mut text = match line.origin() {
+            origin @ '+' | origin @ '-' | origin @ ' ' => origin.to_string(),
+            _ => String::new(),
+        };
+        text.push_str(&String::from_utf8_lossy(line.content()));
+        lines.extend(text.lines().map(str::to_string));
+        true
+    })
+    .map_err(to_io_error)?;
+
+    Ok(lines)
+}
+
+fn to_io_error(err: git2::Error) -> io::Error {
+    io::Error::new(io::ErrorKind::Other, err)
+}
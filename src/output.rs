@@ -0,0 +1,99 @@
+use std::io::{self, Write};
+use std::process::{Child, Command, Stdio};
+
+use atty::Stream;
+
+/// How eagerly delta should page its output. Mirrors bat's `PagingMode`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PagingMode {
+    /// Always pipe through the pager.
+    Always,
+    /// Never pipe through the pager; write straight to stdout.
+    Never,
+    /// Pipe through the pager; when the pager is `less`, pass `-F` so it
+    /// exits at once if the output fits on one screen. Other pagers get no
+    /// such flag.
+    QuitIfOneScreen,
+}
+
+/// Either a pager child process or a locked stdout, depending on
+/// `PagingMode`, whether stdout is a TTY, and the resolved pager command.
+pub enum OutputType {
+    Pager(Child),
+    Stdout(io::StdoutLock<'static>),
+}
+
+impl OutputType {
+    /// Resolve `mode` and `pager_from_config` (set from `Config::pager`,
+    /// itself `$DELTA_PAGER`, then `$PAGER`, then `less`) into an
+    /// `OutputType`. Paging is skipped in favor of stdout directly when
+    /// `mode` is `Never` or stdout is not a TTY (e.g. piped to a file).
+    pub fn from_mode(mode: PagingMode, pager_from_config: Option<&str>) -> io::Result<Self> {
+        use self::PagingMode::*;
+        if !atty::is(Stream::Stdout) {
+            return Ok(OutputType::stdout());
+        }
+        match mode {
+            Always => OutputType::try_pager(false, pager_from_config),
+            QuitIfOneScreen => OutputType::try_pager(true, pager_from_config),
+            Never => Ok(OutputType::stdout()),
+        }
+    }
+
+    fn stdout() -> Self {
+        // Leaked once per process: `io::Stdout` itself is a lightweight
+        // handle onto the real, global stdout, so leaking it to get a
+        // `'static` lock (and thus avoid re-locking per line in `delta()`)
+        // doesn't leak any actual OS resource.
+        let stdout: &'static io::Stdout = Box::leak(Box::new(io::stdout()));
+        OutputType::Stdout(stdout.lock())
+    }
+
+    /// `pager_from_config` is already fully resolved by `paint::get_pager`
+    /// (`$DELTA_PAGER`, then `$PAGER`, then `less`); this only falls back to
+    /// `less` itself when called with `None` directly.
+    fn try_pager(quit_if_one_screen: bool, pager_from_config: Option<&str>) -> io::Result<Self> {
+        let pager = pager_from_config.unwrap_or("less");
+
+        let mut args = pager.split_whitespace();
+        let program = match args.next() {
+            Some(program) => program,
+            None => return Ok(OutputType::stdout()),
+        };
+
+        let mut command = Command::new(program);
+        command.args(args);
+        if program.ends_with("less") {
+            command.arg("-R");
+            if quit_if_one_screen {
+                command.arg("-F");
+            }
+            command.env("LESSCHARSET", "UTF-8");
+        }
+
+        let child = command.stdin(Stdio::piped()).spawn()?;
+        Ok(OutputType::Pager(child))
+    }
+
+    /// A writer for this output: the pager's stdin, or the locked stdout.
+    pub fn handle(&mut self) -> io::Result<&mut dyn Write> {
+        Ok(match *self {
+            OutputType::Pager(ref mut child) => child
+                .stdin
+                .as_mut()
+                .ok_or_else(|| io::Error::new(io::ErrorKind::Other, "failed to open pager stdin"))?,
+            OutputType::Stdout(ref mut stdout) => stdout,
+        })
+    }
+}
+
+impl Drop for OutputType {
+    fn drop(&mut self) {
+        if let OutputType::Pager(ref mut child) = *self {
+            // Close the pager's stdin so it knows there's no more input,
+            // then wait for it to exit before we do.
+            child.stdin.take();
+            let _ = child.wait();
+        }
+    }
+}
@@ -46,6 +46,157 @@ const DARK_THEME_MINUS_COLOR: Color = Color {
     a: 0xff,
 };
 
+/// Mirrors bat's `OutputStyle`: how much decoration, if any, delta adds
+/// around the painted diff text.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OutputStyle {
+    /// No gutter: just the syntax-highlighted diff text (current behavior).
+    Plain,
+    /// A left-hand gutter showing old/new line numbers.
+    LineNumbers,
+    /// Line numbers plus any other decorations delta supports.
+    Full,
+}
+
+impl OutputStyle {
+    fn has_line_numbers(self) -> bool {
+        match self {
+            OutputStyle::Plain => false,
+            OutputStyle::LineNumbers | OutputStyle::Full => true,
+        }
+    }
+}
+
+/// Width, in columns, of each of the two line-number fields in the gutter.
+/// 6 digits covers files up to 999,999 lines without the numbers
+/// overflowing their field.
+const NUMBER_COL_WIDTH: usize = 6;
+
+/// Width, in columns, of the rendered line-number gutter: two
+/// `NUMBER_COL_WIDTH`-wide number columns, a dim "│" separator, and the
+/// spacing around them. Derived from `NUMBER_COL_WIDTH` so this can't drift
+/// out of sync with `write_line_number_gutter`'s formatting.
+const GUTTER_WIDTH: usize = 2 * NUMBER_COL_WIDTH + 3;
+
+/// How many colors the terminal can display, and therefore which escape
+/// sequences `paint()`/`paint_text()` should emit. Mirrors bat's `true_color`
+/// flag, extended with a 256-color fallback.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ColorDepth {
+    /// `\x1b[38;2;R;G;Bm` / `\x1b[48;2;R;G;Bm`
+    TrueColor,
+    /// `\x1b[38;5;Nm` / `\x1b[48;5;Nm`, `N` the nearest xterm-256 palette index.
+    Color256,
+    /// The 16 standard ANSI colors (`\x1b[3Nm`/`\x1b[9Nm`).
+    Color16,
+}
+
+impl ColorDepth {
+    /// Auto-detect from `COLORTERM`/`TERM`, like bat's `true_color` flag.
+    pub fn detect() -> Self {
+        match std::env::var("COLORTERM") {
+            Ok(val) if val == "truecolor" || val == "24bit" => return ColorDepth::TrueColor,
+            _ => (),
+        }
+        match std::env::var("TERM") {
+            Ok(ref term) if term.contains("256color") => ColorDepth::Color256,
+            _ => ColorDepth::Color16,
+        }
+    }
+}
+
+/// The 16 standard ANSI colors: the 8 normal ones in `\x1b[3Nm` order (black,
+/// red, green, yellow, blue, magenta, cyan, white), then their "bright"
+/// variants in the same order. The target set for `nearest_ansi_16`.
+const ANSI_16_PALETTE: [(u8, u8, u8); 16] = [
+    (0x00, 0x00, 0x00), // black
+    (0x80, 0x00, 0x00), // red
+    (0x00, 0x80, 0x00), // green
+    (0x80, 0x80, 0x00), // yellow
+    (0x00, 0x00, 0x80), // blue
+    (0x80, 0x00, 0x80), // magenta
+    (0x00, 0x80, 0x80), // cyan
+    (0xc0, 0xc0, 0xc0), // white
+    (0x80, 0x80, 0x80), // bright black
+    (0xff, 0x00, 0x00), // bright red
+    (0x00, 0xff, 0x00), // bright green
+    (0xff, 0xff, 0x00), // bright yellow
+    (0x00, 0x00, 0xff), // bright blue
+    (0xff, 0x00, 0xff), // bright magenta
+    (0x00, 0xff, 0xff), // bright cyan
+    (0xff, 0xff, 0xff), // bright white
+];
+
+fn squared_distance(a: (u8, u8, u8), b: Color) -> u32 {
+    let dr = a.0 as i32 - b.r as i32;
+    let dg = a.1 as i32 - b.g as i32;
+    let db = a.2 as i32 - b.b as i32;
+    (dr * dr + dg * dg + db * db) as u32
+}
+
+/// Index, in `0..16`, of the ANSI-16 color nearest `color` by squared RGB
+/// distance. The SGR code is `30 + n` (or `90 + (n - 8)` for the bright
+/// half) for foreground, `40 + n`/`100 + (n - 8)` for background.
+fn nearest_ansi_16(color: Color) -> u8 {
+    ANSI_16_PALETTE
+        .iter()
+        .enumerate()
+        .min_by_key(|(_, &rgb)| squared_distance(rgb, color))
+        .map(|(i, _)| i as u8)
+        .unwrap_or(7)
+}
+
+/// The 6 steps (0, 95, 135, 175, 215, 255) used by each of the three axes of the xterm
+/// 256-color 6×6×6 cube.
+const CUBE_STEPS: [u8; 6] = [0x00, 0x5f, 0x87, 0xaf, 0xd7, 0xff];
+
+/// The 24-step xterm grayscale ramp, indices 232..=255, from 8 to 238.
+fn grayscale_step(n: u8) -> u8 {
+    8 + n * 10
+}
+
+fn nearest_cube_step(c: u8) -> u8 {
+    CUBE_STEPS
+        .iter()
+        .enumerate()
+        .min_by_key(|&(_, &step)| (step as i32 - c as i32).abs())
+        .map(|(i, _)| i as u8)
+        .unwrap_or(0)
+}
+
+/// Convert a `syntect` RGB color to the nearest xterm-256 palette index
+/// (`\x1b[38;5;Nm`), choosing between the 6×6×6 color cube (indices 16..=231)
+/// and the 24-step grayscale ramp (indices 232..=255) by minimal squared RGB
+/// distance.
+fn nearest_xterm_256(color: Color) -> u8 {
+    let cube_r = nearest_cube_step(color.r);
+    let cube_g = nearest_cube_step(color.g);
+    let cube_b = nearest_cube_step(color.b);
+    let cube_rgb = (
+        CUBE_STEPS[cube_r as usize],
+        CUBE_STEPS[cube_g as usize],
+        CUBE_STEPS[cube_b as usize],
+    );
+    let cube_index = 16 + 36 * cube_r + 6 * cube_g + cube_b;
+
+    let gray_n = (0..24)
+        .min_by_key(|&n| (grayscale_step(n) as i32 - color_luminance(color) as i32).abs())
+        .unwrap_or(0);
+    let gray_level = grayscale_step(gray_n);
+    let gray_rgb = (gray_level, gray_level, gray_level);
+    let gray_index = 232 + gray_n;
+
+    if squared_distance(cube_rgb, color) <= squared_distance(gray_rgb, color) {
+        cube_index
+    } else {
+        gray_index
+    }
+}
+
+fn color_luminance(color: Color) -> u8 {
+    ((color.r as u32 + color.g as u32 + color.b as u32) / 3) as u8
+}
+
 pub struct Config<'a> {
     pub theme: &'a Theme,
     pub plus_color: Color,
@@ -53,7 +204,17 @@ pub struct Config<'a> {
     pub syntax_set: &'a SyntaxSet,
     pub width: Option<usize>,
     pub highlight_removed: bool,
-    pub pager: &'a str,
+    pub pager: String,
+    pub paging_mode: crate::output::PagingMode,
+    pub style: OutputStyle,
+    pub color_depth: ColorDepth,
+}
+
+/// Resolve the pager command: `$DELTA_PAGER`, then `$PAGER`, then `less`.
+fn get_pager() -> String {
+    std::env::var("DELTA_PAGER")
+        .or_else(|_| std::env::var("PAGER"))
+        .unwrap_or_else(|_| "less".to_string())
 }
 
 pub fn get_config<'a>(
@@ -65,6 +226,9 @@ pub fn get_config<'a>(
     minus_color_str: &Option<String>,
     highlight_removed: bool,
     width: Option<usize>,
+    style: OutputStyle,
+    color_depth: Option<ColorDepth>,
+    paging_mode: crate::output::PagingMode,
 ) -> Config<'a> {
     let theme_name = match theme {
         Some(ref theme) => theme,
@@ -98,13 +262,31 @@ pub fn get_config<'a>(
                 DARK_THEME_MINUS_COLOR
             }
         }),
-        width: width,
+        width: width.map(|w| {
+            if style.has_line_numbers() {
+                w.saturating_sub(GUTTER_WIDTH)
+            } else {
+                w
+            }
+        }),
         highlight_removed: highlight_removed,
         syntax_set: &syntax_set,
-        pager: "less",
+        pager: get_pager(),
+        paging_mode: paging_mode,
+        style: style,
+        color_depth: color_depth.unwrap_or_else(ColorDepth::detect),
     }
 }
 
+/// The old-file/new-file line numbers to render in the gutter for a run of
+/// painted lines. `None` in either slot means "leave that column blank", used
+/// for lines that only exist on one side of the diff.
+#[derive(Debug, Clone, Copy)]
+pub struct LineNumbers {
+    pub minus: Option<usize>,
+    pub plus: Option<usize>,
+}
+
 pub struct Painter<'a> {
     pub minus_lines: Vec<String>,
     pub plus_lines: Vec<String>,
@@ -112,6 +294,8 @@ pub struct Painter<'a> {
     pub syntax: Option<&'a SyntaxReference>,
     pub config: &'a Config<'a>,
     pub output_buffer: String,
+    pub minus_line_number: usize,
+    pub plus_line_number: usize,
 }
 
 impl<'a> Painter<'a> {
@@ -123,16 +307,28 @@ impl<'a> Painter<'a> {
         if self.is_empty() {
             return Ok(());
         }
+        let n_minus_lines = self.minus_lines.len();
+        let minus_line_numbers = LineNumbers {
+            minus: Some(self.minus_line_number - n_minus_lines),
+            plus: None,
+        };
         self.paint_and_emit_text(
             self.minus_lines.join("\n"),
             Some(self.config.minus_color),
             self.config.highlight_removed,
+            minus_line_numbers,
         )?;
         self.minus_lines.clear();
+        let n_plus_lines = self.plus_lines.len();
+        let plus_line_numbers = LineNumbers {
+            minus: None,
+            plus: Some(self.plus_line_number - n_plus_lines),
+        };
         self.paint_and_emit_text(
             self.plus_lines.join("\n"),
             Some(self.config.plus_color),
             true,
+            plus_line_numbers,
         )?;
         self.plus_lines.clear();
         Ok(())
@@ -143,6 +339,7 @@ impl<'a> Painter<'a> {
         text: String,
         background_color: Option<Color>,
         apply_syntax_highlighting: bool,
+        line_numbers: LineNumbers,
     ) -> std::io::Result<()> {
         paint_text(
             text,
@@ -150,6 +347,7 @@ impl<'a> Painter<'a> {
             background_color,
             self.config,
             apply_syntax_highlighting,
+            line_numbers,
             &mut self.output_buffer,
         );
         writeln!(self.writer, "{}", self.output_buffer)?;
@@ -167,6 +365,7 @@ pub fn paint_text(
     background_color: Option<Color>,
     config: &Config,
     apply_syntax_highlighting: bool,
+    mut line_numbers: LineNumbers,
     buf: &mut String,
 ) {
     use std::fmt::Write;
@@ -176,19 +375,47 @@ pub fn paint_text(
         // TODO:
         // 1. pad right
         // 2. remove +- in first column
-        match background_color {
-            Some(background_color) => {
-                write!(
-                    buf,
-                    "\x1b[48;2;{};{};{}m",
-                    background_color.r, background_color.g, background_color.b
-                )
-                .unwrap();
-            }
-            None => (),
+        write_line_number_gutter(&mut line_numbers, config.style, buf);
+        if let Some(background_color) = background_color {
+            write_background_escape(buf, background_color, config.color_depth);
         }
         let ranges: Vec<(Style, &str)> = highlighter.highlight(line, &config.syntax_set);
-        paint_ranges(&ranges[..], None, apply_syntax_highlighting, buf)
+        paint_ranges(
+            &ranges[..],
+            None,
+            apply_syntax_highlighting,
+            config.color_depth,
+            buf,
+        )
+    }
+}
+
+/// Write the left-hand old/new line-number gutter for one displayed line,
+/// then advance whichever of `line_numbers.minus`/`line_numbers.plus` is
+/// present by one. A no-op under `OutputStyle::Plain`.
+fn write_line_number_gutter(line_numbers: &mut LineNumbers, style: OutputStyle, buf: &mut String) {
+    use std::fmt::Write;
+    if !style.has_line_numbers() {
+        return;
+    }
+    let minus = line_numbers
+        .minus
+        .map(|n| n.to_string())
+        .unwrap_or_default();
+    let plus = line_numbers.plus.map(|n| n.to_string()).unwrap_or_default();
+    write!(
+        buf,
+        "\x1b[2m{:>width$}{:>width$} \u{2502}\x1b[0m ",
+        minus,
+        plus,
+        width = NUMBER_COL_WIDTH
+    )
+    .unwrap();
+    if let Some(n) = line_numbers.minus.as_mut() {
+        *n += 1;
+    }
+    if let Some(n) = line_numbers.plus.as_mut() {
+        *n += 1;
     }
 }
 
@@ -197,6 +424,7 @@ fn paint_ranges(
     foreground_style_ranges: &[(Style, &str)],
     background_color: Option<Color>,
     apply_syntax_highlighting: bool,
+    color_depth: ColorDepth,
     buf: &mut String,
 ) -> () {
     for &(ref style, text) in foreground_style_ranges.iter() {
@@ -208,38 +436,70 @@ fn paint_ranges(
                 None
             },
             background_color,
+            color_depth,
             buf,
         );
     }
 }
 
+/// Write the background-color escape sequence for `color` at `color_depth`.
+fn write_background_escape(buf: &mut String, color: Color, color_depth: ColorDepth) {
+    use std::fmt::Write;
+    match color_depth {
+        ColorDepth::TrueColor => {
+            write!(buf, "\x1b[48;2;{};{};{}m", color.r, color.g, color.b).unwrap();
+        }
+        ColorDepth::Color256 => {
+            write!(buf, "\x1b[48;5;{}m", nearest_xterm_256(color)).unwrap();
+        }
+        ColorDepth::Color16 => {
+            let n = nearest_ansi_16(color);
+            if n < 8 {
+                write!(buf, "\x1b[{}m", 40 + n).unwrap();
+            } else {
+                write!(buf, "\x1b[{}m", 100 + (n - 8)).unwrap();
+            }
+        }
+    }
+}
+
+/// Write the foreground-color escape sequence for `color` at `color_depth`.
+fn write_foreground_escape(buf: &mut String, color: Color, color_depth: ColorDepth) {
+    use std::fmt::Write;
+    match color_depth {
+        ColorDepth::TrueColor => {
+            write!(buf, "\x1b[38;2;{};{};{}m", color.r, color.g, color.b).unwrap();
+        }
+        ColorDepth::Color256 => {
+            write!(buf, "\x1b[38;5;{}m", nearest_xterm_256(color)).unwrap();
+        }
+        ColorDepth::Color16 => {
+            let n = nearest_ansi_16(color);
+            if n < 8 {
+                write!(buf, "\x1b[{}m", 30 + n).unwrap();
+            } else {
+                write!(buf, "\x1b[{}m", 90 + (n - 8)).unwrap();
+            }
+        }
+    }
+}
+
 /// Write text to buffer with color escape codes applied.
 fn paint(
     text: &str,
     foreground_color: Option<Color>,
     background_color: Option<Color>,
+    color_depth: ColorDepth,
     buf: &mut String,
 ) -> () {
     use std::fmt::Write;
-    match background_color {
-        Some(background_color) => {
-            write!(
-                buf,
-                "\x1b[48;2;{};{};{}m",
-                background_color.r, background_color.g, background_color.b
-            )
-            .unwrap();
-        }
-        None => (),
+    if let Some(background_color) = background_color {
+        write_background_escape(buf, background_color, color_depth);
     }
     match foreground_color {
         Some(foreground_color) => {
-            write!(
-                buf,
-                "\x1b[38;2;{};{};{}m{}",
-                foreground_color.r, foreground_color.g, foreground_color.b, text
-            )
-            .unwrap();
+            write_foreground_escape(buf, foreground_color, color_depth);
+            write!(buf, "{}", text).unwrap();
         }
         None => {
             write!(buf, "{}", text).unwrap();
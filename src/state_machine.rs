@@ -1,8 +1,8 @@
 use console::strip_ansi_codes;
 
 use crate::assets::HighlightingAssets;
-use crate::output::{OutputType, PagingMode};
-use crate::paint::{Config, Painter};
+use crate::output::OutputType;
+use crate::paint::{Config, LineNumbers, Painter};
 use crate::parse_diff::get_file_extension_from_diff_line;
 
 #[derive(Debug, PartialEq)]
@@ -28,6 +28,29 @@ pub enum State {
 // | HunkMinus | flush, emit | flush, emit | flush, emit | flush, emit | push        | push     |
 // | HunkPlus  | flush, emit | flush, emit | flush, emit | flush, emit | flush, push | push     |
 
+/// Parse a hunk header of the form `@@ -old_start,old_count +new_start,new_count @@ ...`
+/// and return the starting line number of the old file and of the new file.
+fn parse_hunk_header(line: &str) -> (usize, usize) {
+    let parse_start = |field: &str| -> usize {
+        field
+            .trim_start_matches(|c| c == '-' || c == '+')
+            .split(',')
+            .next()
+            .unwrap_or("1")
+            .parse()
+            .unwrap_or(1)
+    };
+    let mut fields = line
+        .trim_start_matches("@@ ")
+        .splitn(2, " @@")
+        .next()
+        .unwrap_or("")
+        .split_whitespace();
+    let minus_start = fields.next().map(parse_start).unwrap_or(1);
+    let plus_start = fields.next().map(parse_start).unwrap_or(1);
+    (minus_start, plus_start)
+}
+
 pub fn delta(
     lines: impl Iterator<Item = String>,
     config: &Config,
@@ -35,7 +58,7 @@ pub fn delta(
 ) -> std::io::Result<()> {
     let mut line: String;
     let mut output_type =
-        OutputType::from_mode(PagingMode::QuitIfOneScreen, Some(config.pager)).unwrap();
+        OutputType::from_mode(config.paging_mode, Some(&config.pager)).unwrap();
     let mut painter = Painter {
         minus_lines: Vec::new(),
         plus_lines: Vec::new(),
@@ -43,6 +66,8 @@ pub fn delta(
         writer: output_type.handle().unwrap(),
         syntax: None,
         config: config,
+        minus_line_number: 0,
+        plus_line_number: 0,
     };
 
     let mut state = State::Unknown;
@@ -62,6 +87,9 @@ pub fn delta(
             state = State::Commit;
         } else if line.starts_with("@@") {
             state = State::HunkMeta;
+            let (minus_start, plus_start) = parse_hunk_header(&line);
+            painter.minus_line_number = minus_start;
+            painter.plus_line_number = plus_start;
         } else if (state == State::HunkMeta
             || state == State::HunkZero
             || state == State::HunkMinus
@@ -74,16 +102,24 @@ pub fn delta(
                         painter.paint_and_emit_buffered_lines()?;
                     }
                     painter.minus_lines.push(line);
+                    painter.minus_line_number += 1;
                     state = State::HunkMinus;
                 }
                 Some('+') => {
                     painter.plus_lines.push(line);
+                    painter.plus_line_number += 1;
                     state = State::HunkPlus;
                 }
                 _ => {
                     painter.paint_and_emit_buffered_lines()?;
                     state = State::HunkZero;
-                    painter.paint_and_emit_text(line, None, true)?;
+                    let line_numbers = LineNumbers {
+                        minus: Some(painter.minus_line_number),
+                        plus: Some(painter.plus_line_number),
+                    };
+                    painter.minus_line_number += 1;
+                    painter.plus_line_number += 1;
+                    painter.paint_and_emit_text(line, None, true, line_numbers)?;
                 }
             };
             continue;